@@ -1,18 +1,33 @@
-use std::sync::mpsc::{ Sender, Receiver, TryRecvError, channel };
+use std::time::{ Duration, Instant };
+use std::collections::VecDeque;
+use std::sync::{ Arc, Mutex };
+use std::pin::Pin;
+use std::task::{ Context, Poll };
+use crossbeam_channel::{ Sender, Receiver, bounded, unbounded, after, never, select };
+use futures_channel::mpsc::{ UnboundedSender, UnboundedReceiver, unbounded as unbounded_async };
+use futures_util::StreamExt;
+use futures_util::sink::Sink;
 
 mod controller;
 pub mod evaluation;
 mod misa;
 pub mod moves;
 mod tree;
+mod work_queue;
 
 use libtetris::*;
-use crate::tree::Tree;
+use crate::tree::{ Tree, Leaf, ExpansionResult };
 use crate::moves::Move;
 use crate::evaluation::Evaluator;
+use crate::work_queue::ArrayQueue;
 
 pub use crate::controller::Controller;
 
+/// Default bound on how many `BotMsg` commands (`add_next_piece`, `reset`, etc.) can be queued up
+/// before the bot thread has drained them. Used by `misa_glue`, which has no `Options` to take a
+/// `command_capacity` from.
+const DEFAULT_COMMAND_CAPACITY: usize = 16;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Options {
     pub mode: crate::moves::MovementMode,
@@ -20,6 +35,18 @@ pub struct Options {
     pub speculate: bool,
     pub min_nodes: usize,
     pub max_nodes: usize,
+    /// How many worker threads to use to expand the search tree.
+    ///
+    /// `1` (the default) runs the search on the bot thread alone, same as before this option
+    /// existed. Values greater than `1` spread leaf expansion across that many extra worker
+    /// threads; this requires the evaluator to implement `Clone`, since each worker needs its own
+    /// copy.
+    pub threads: usize,
+    /// How many `BotMsg` commands can be queued up before the bot thread has drained them.
+    ///
+    /// `try_add_next_piece`/`try_reset` report `CommandError::Full` once this is exceeded instead
+    /// of letting the queue grow without bound.
+    pub command_capacity: usize,
 }
 
 impl Default for Options {
@@ -29,98 +56,194 @@ impl Default for Options {
             use_hold: true,
             speculate: true,
             min_nodes: 0,
-            max_nodes: std::usize::MAX
+            max_nodes: std::usize::MAX,
+            threads: 1,
+            command_capacity: DEFAULT_COMMAND_CAPACITY
         }
     }
 }
 
+/// A synchronous, polling interface to a running bot.
+///
+/// This is a thin wrapper around `AsyncInterface` that polls it instead of awaiting it, so it
+/// suits clients driven by a synchronous game loop rather than an async runtime.
 pub struct Interface {
-    send: Sender<BotMsg>,
-    recv: Receiver<BotResult>,
-    dead: bool,
+    inner: AsyncInterface,
     mv: Option<Move>
 }
 
+/// How many of the most recent `Info` snapshots are retained for newly-lagged subscribers.
+const INFO_RETENTION: usize = 32;
+
+/// A broadcast queue of `Info` snapshots, retaining the most recent `INFO_RETENTION` of them so
+/// that any number of `InfoReceiver`s can independently read the bot's live thinking.
+struct InfoBroadcast {
+    buffer: Mutex<VecDeque<(u64, Info)>>,
+    next_seq: Mutex<u64>
+}
+
+impl InfoBroadcast {
+    fn new() -> Self {
+        InfoBroadcast {
+            buffer: Mutex::new(VecDeque::with_capacity(INFO_RETENTION)),
+            next_seq: Mutex::new(0)
+        }
+    }
+
+    fn push(&self, info: Info) {
+        let mut buffer = self.buffer.lock().unwrap();
+        let mut next_seq = self.next_seq.lock().unwrap();
+        if buffer.len() == INFO_RETENTION {
+            buffer.pop_front();
+        }
+        buffer.push_back((*next_seq, info));
+        *next_seq += 1;
+    }
+
+    fn latest_seq(&self) -> u64 {
+        *self.next_seq.lock().unwrap()
+    }
+}
+
+/// A subscription to a bot's stream of `Info` snapshots, created with `Interface::subscribe_info`.
+///
+/// Each `InfoReceiver` tracks its own read cursor into the broadcast queue, so multiple
+/// subscribers can consume the stream independently and at their own pace.
+pub struct InfoReceiver {
+    broadcast: Arc<InfoBroadcast>,
+    next: u64
+}
+
+impl InfoReceiver {
+    /// Retrieves the next `Info` snapshot, if one is available.
+    ///
+    /// If this receiver fell behind and some snapshots were evicted from the retained window
+    /// before it could read them, this returns `Err(InfoRecvError::Lagged(n))` where `n` is the
+    /// number of snapshots that were skipped, and advances the cursor to the oldest snapshot
+    /// still available rather than stalling the bot thread.
+    pub fn try_recv(&mut self) -> Result<Info, InfoRecvError> {
+        let buffer = self.broadcast.buffer.lock().unwrap();
+        let &(oldest_seq, _) = match buffer.front() {
+            Some(entry) => entry,
+            None => return Err(InfoRecvError::Empty)
+        };
+
+        if self.next < oldest_seq {
+            let skipped = oldest_seq - self.next;
+            self.next = oldest_seq;
+            return Err(InfoRecvError::Lagged(skipped));
+        }
+
+        match buffer.get((self.next - oldest_seq) as usize) {
+            Some((_, info)) => {
+                self.next += 1;
+                Ok(info.clone())
+            }
+            None => Err(InfoRecvError::Empty)
+        }
+    }
+}
+
+/// The error returned by `InfoReceiver::try_recv`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InfoRecvError {
+    /// No snapshot newer than the last one received is available yet.
+    Empty,
+    /// The receiver fell behind and this many snapshots were evicted before it could read them.
+    Lagged(u64)
+}
+
 impl Interface {
     /// Launches a bot thread with the specified starting board and options.
+    ///
+    /// The evaluator must implement `Clone` because `options.threads > 1` gives each search
+    /// worker its own copy to evaluate with.
     pub fn launch(
-        board: Board, options: Options, evaluator: impl Evaluator + Send + 'static
+        board: Board, options: Options, evaluator: impl Evaluator + Clone + Send + 'static
     ) -> Self {
-        let (bot_send, recv) = channel();
-        let (send, bot_recv) = channel();
-        std::thread::spawn(move || run(bot_recv, bot_send, board, evaluator, options));
-
-        Interface {
-            send, recv, dead: false, mv: None
-        }
+        Interface { inner: AsyncInterface::launch(board, options, evaluator), mv: None }
     }
 
     pub fn misa_glue(board: Board) -> Self {
-        let (bot_send, recv) = channel();
-        let (send, bot_recv) = channel();
-        std::thread::spawn(move || misa::glue(bot_recv, bot_send, board));
+        Interface { inner: AsyncInterface::misa_glue(board), mv: None }
+    }
 
-        Interface {
-            send, recv, dead: false, mv: None
-        }
+    /// Subscribes to the bot's stream of `Info` snapshots.
+    ///
+    /// Any number of subscribers can be created; each receives every snapshot retained since it
+    /// subscribed, independently of how fast other subscribers consume them. Snapshots reach the
+    /// subscribers only as this `Interface` collects them from the bot thread, which it does when
+    /// `poll_next_move` is called.
+    pub fn subscribe_info(&self) -> InfoReceiver {
+        self.inner.subscribe_info()
     }
 
     pub fn misa_prepare_next_move(&mut self) {
-        if self.send.send(BotMsg::PrepareNextMove).is_err() {
-            self.dead = true;
+        if self.inner.send.send(BotMsg::PrepareNextMove).is_err() {
+            self.inner.dead = true;
         }
     }
 
     /// Returns true if all possible piece placement sequences result in death, or some kind of
     /// error occured that crashed the bot thread.
     pub fn is_dead(&self) -> bool {
-        self.dead
+        self.inner.dead
     }
 
     fn poll_bot(&mut self) {
         loop {
-            match self.recv.try_recv() {
+            match self.inner.recv.try_recv() {
                 Ok(BotResult::Move(mv)) => self.mv = Some(mv),
-                Ok(BotResult::BotInfo(_)) => { /* TODO */ },
-                Err(TryRecvError::Empty) => break,
-                Err(TryRecvError::Disconnected) => {
-                    self.dead = true;
+                Ok(BotResult::BotInfo(info)) => self.inner.info.push(info),
+                Err(e) if e.is_closed() => {
+                    self.inner.dead = true;
                     break
                 }
+                Err(_) => break // no message ready yet
             }
         }
     }
 
     /// Request the bot to provide a move as soon as possible.
-    /// 
+    ///
     /// In most cases, "as soon as possible" is a very short amount of time, and is only longer if
     /// the provided lower limit on thinking has not been reached yet or if the bot cannot provide
     /// a move yet, usually because it lacks information on the next pieces.
-    /// 
+    ///
     /// For example, in a game with zero piece previews and hold enabled, the bot will never be able
     /// to provide the first move because it cannot know what piece it will be placing if it chooses
     /// to hold. Another example: in a game with zero piece previews and hold disabled, the bot
     /// will only be able to provide a move after the current piece spawns and you provide the new
     /// piece information to the bot using `add_next_piece`.
-    /// 
+    ///
     /// It is recommended that you wait to call this function until after the current piece spawns
     /// and you update the queue using `add_next_piece`, as this will allow speculation to be
     /// resolved and at least one thinking cycle to run.
-    /// 
+    ///
     /// Once a move is chosen, the bot will update its internal state to the result of the piece
     /// being placed correctly and the move will become available by calling `poll_next_move`.
     pub fn request_next_move(&mut self) {
-        if self.send.send(BotMsg::NextMove).is_err() {
-            self.dead = true;
-        }
+        self.inner.request_next_move();
+    }
+
+    /// Request the bot to provide a move once it has thought for approximately the given amount
+    /// of time.
+    ///
+    /// Unlike `request_next_move`, which returns a move as soon as `min_nodes` is satisfied, this
+    /// gives the bot a wall-clock deadline: it keeps searching until `duration` has elapsed, then
+    /// returns its current best move. If `min_nodes` has not been reached by the deadline, the bot
+    /// keeps thinking a little longer rather than returning a move it has little confidence in,
+    /// up to a hard cap so a client is never left waiting indefinitely.
+    pub fn request_next_move_within(&mut self, duration: Duration) {
+        self.inner.request_next_move_within(duration);
     }
 
     /// Checks to see if the bot has provided the previously requested move yet.
-    /// 
+    ///
     /// The returned move contains both a path and the expected location of the placed piece. The
     /// returned path is reasonably good, but you might want to use your own pathfinder to, for
     /// example, exploit movement intricacies in the game you're playing.
-    /// 
+    ///
     /// If the piece couldn't be placed in the expected location, you must call `reset` to reset the
     /// game field, back-to-back status, and combo values.
     pub fn poll_next_move(&mut self) -> Option<Move> {
@@ -129,26 +252,210 @@ impl Interface {
     }
 
     /// Adds a new piece to the end of the queue.
-    /// 
+    ///
     /// If speculation is enabled, the piece must be in the bag. For example, if you start a new
     /// game with starting sequence IJOZT, the first time you call this function you can only
     /// provide either an L or an S piece.
     pub fn add_next_piece(&mut self, piece: Piece) {
-        if self.send.send(BotMsg::NewPiece(piece)).is_err() {
-            self.dead = true;
-        }
+        self.inner.add_next_piece(piece);
+    }
+
+    /// Same as `add_next_piece`, but reports `CommandError::Full` instead of blocking if the bot
+    /// hasn't drained its command queue yet, and `CommandError::Disconnected` if it has died.
+    pub fn try_add_next_piece(&mut self, piece: Piece) -> Result<(), CommandError> {
+        self.inner.try_add_next_piece(piece)
     }
 
     /// Resets the playfield, back-to-back status, and combo count.
-    /// 
+    ///
     /// This should only be used when garbage is received or when your client could not place the
     /// piece in the correct position for some reason (e.g. 15 move rule), since this forces the
     /// bot to throw away previous computations.
-    /// 
+    ///
     /// Note: combo is not the same as the displayed combo in guideline games. Here, it is better
     /// thought of as the number of pieces that have been placed that cleared lines in a row. So,
     /// generally speaking, if you break your combo, use 0 here; if you just clear a line, use 1
     /// here; and if "x Combo" appears on the screen, use x+1 here.
+    pub fn reset(&mut self, field: [[bool; 10]; 40], b2b_active: bool, combo: u32) {
+        self.inner.reset(field, b2b_active, combo);
+    }
+
+    /// Same as `reset`, but reports `CommandError::Full` instead of blocking if the bot hasn't
+    /// drained its command queue yet, and `CommandError::Disconnected` if it has died.
+    pub fn try_reset(
+        &mut self, field: [[bool; 10]; 40], b2b_active: bool, combo: u32
+    ) -> Result<(), CommandError> {
+        self.inner.try_reset(field, b2b_active, combo)
+    }
+}
+
+/// An async interface to a running bot, for clients embedded in an async runtime.
+///
+/// Unlike `Interface`, which must be polled, `AsyncInterface::next_move` returns a future that
+/// resolves as soon as the bot sends its move, and `is_dead`'s death condition surfaces through
+/// that same future resolving to `Err(Dead)` instead of needing a separate check. `add_next_piece`
+/// and `reset` are also available as a `Sink<Command>` for clients that want to feed the bot as
+/// part of a `Stream`/`Sink` pipeline rather than calling them directly.
+pub struct AsyncInterface {
+    send: Sender<BotMsg>,
+    recv: UnboundedReceiver<BotResult>,
+    info: Arc<InfoBroadcast>,
+    dead: bool
+}
+
+/// The bot thread died, either because every piece placement sequence leads to death or because
+/// it encountered an unrecoverable error.
+#[derive(Copy, Clone, Debug)]
+pub struct Dead;
+
+/// The error returned by `try_add_next_piece`/`try_reset`, mirroring
+/// `crossbeam_channel::TrySendError`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CommandError {
+    /// The bot hasn't drained its command queue fast enough; wait and try again.
+    Full,
+    /// The bot thread is dead.
+    Disconnected
+}
+
+/// The commands accepted by `AsyncInterface`'s `Sink` impl: `add_next_piece` and `reset`, the two
+/// that describe an ongoing stream of game state updates, as opposed to one-off requests like
+/// `request_next_move`.
+#[derive(Clone, Debug)]
+pub enum Command {
+    NewPiece(Piece),
+    // Boxed so this variant doesn't dwarf `NewPiece`'s, the same reason `BotMsg::Reset` would if
+    // it weren't already a pre-existing exception.
+    Reset {
+        field: Box<[[bool; 10]; 40]>,
+        b2b_active: bool,
+        combo: u32
+    }
+}
+
+impl Sink<Command> for AsyncInterface {
+    type Error = Dead;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Dead>> {
+        if self.dead { Poll::Ready(Err(Dead)) } else { Poll::Ready(Ok(())) }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Command) -> Result<(), Dead> {
+        let this = self.get_mut();
+        let msg = match item {
+            Command::NewPiece(piece) => BotMsg::NewPiece(piece),
+            Command::Reset { field, b2b_active, combo } => BotMsg::Reset { field: *field, b2b: b2b_active, combo }
+        };
+        if this.send.send(msg).is_err() {
+            this.dead = true;
+            return Err(Dead);
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Dead>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Dead>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncInterface {
+    /// Launches a bot thread with the specified starting board and options.
+    ///
+    /// The evaluator must implement `Clone` because `options.threads > 1` gives each search
+    /// worker its own copy to evaluate with.
+    pub fn launch(
+        board: Board, options: Options, evaluator: impl Evaluator + Clone + Send + 'static
+    ) -> Self {
+        let (bot_send, recv) = unbounded_async();
+        let (send, bot_recv) = bounded(options.command_capacity);
+        std::thread::spawn(move || if options.threads > 1 {
+            run_parallel(bot_recv, bot_send, board, evaluator, options)
+        } else {
+            run(bot_recv, bot_send, board, evaluator, options)
+        });
+
+        AsyncInterface {
+            send, recv, info: Arc::new(InfoBroadcast::new()), dead: false
+        }
+    }
+
+    pub fn misa_glue(board: Board) -> Self {
+        let (bot_send, recv) = unbounded_async();
+        let (send, bot_recv) = bounded(DEFAULT_COMMAND_CAPACITY);
+        std::thread::spawn(move || misa::glue(bot_recv, bot_send, board));
+
+        AsyncInterface {
+            send, recv, info: Arc::new(InfoBroadcast::new()), dead: false
+        }
+    }
+
+    /// Subscribes to the bot's stream of `Info` snapshots. See `Interface::subscribe_info`.
+    pub fn subscribe_info(&self) -> InfoReceiver {
+        InfoReceiver {
+            broadcast: self.info.clone(),
+            next: self.info.latest_seq()
+        }
+    }
+
+    /// Returns true if all possible piece placement sequences result in death, or some kind of
+    /// error occured that crashed the bot thread.
+    pub fn is_dead(&self) -> bool {
+        self.dead
+    }
+
+    /// Request the bot to provide a move as soon as possible. See `Interface::request_next_move`.
+    pub fn request_next_move(&mut self) {
+        if self.send.send(BotMsg::NextMove).is_err() {
+            self.dead = true;
+        }
+    }
+
+    /// Request the bot to provide a move once it has thought for approximately the given amount
+    /// of time. See `Interface::request_next_move_within`.
+    pub fn request_next_move_within(&mut self, duration: Duration) {
+        if self.send.send(BotMsg::NextMoveWithin(duration)).is_err() {
+            self.dead = true;
+        }
+    }
+
+    /// Resolves once the bot sends the move requested via `request_next_move` or
+    /// `request_next_move_within`, or to `Err(Dead)` if the bot thread dies first.
+    pub async fn next_move(&mut self) -> Result<Move, Dead> {
+        loop {
+            match self.recv.next().await {
+                Some(BotResult::Move(mv)) => return Ok(mv),
+                Some(BotResult::BotInfo(info)) => self.info.push(info),
+                None => {
+                    self.dead = true;
+                    return Err(Dead)
+                }
+            }
+        }
+    }
+
+    /// Adds a new piece to the end of the queue. See `Interface::add_next_piece`.
+    pub fn add_next_piece(&mut self, piece: Piece) {
+        if self.send.send(BotMsg::NewPiece(piece)).is_err() {
+            self.dead = true;
+        }
+    }
+
+    /// Same as `add_next_piece`, but reports `CommandError::Full` instead of blocking if the bot
+    /// hasn't drained its command queue yet. See `Interface::try_add_next_piece`.
+    pub fn try_add_next_piece(&mut self, piece: Piece) -> Result<(), CommandError> {
+        self.send.try_send(BotMsg::NewPiece(piece)).map_err(|e| if e.is_full() {
+            CommandError::Full
+        } else {
+            self.dead = true;
+            CommandError::Disconnected
+        })
+    }
+
+    /// Resets the playfield, back-to-back status, and combo count. See `Interface::reset`.
     pub fn reset(&mut self, field: [[bool; 10]; 40], b2b_active: bool, combo: u32) {
         if self.send.send(BotMsg::Reset {
             field, b2b: b2b_active, combo
@@ -156,6 +463,21 @@ impl Interface {
             self.dead = true;
         }
     }
+
+    /// Same as `reset`, but reports `CommandError::Full` instead of blocking if the bot hasn't
+    /// drained its command queue yet. See `Interface::try_reset`.
+    pub fn try_reset(
+        &mut self, field: [[bool; 10]; 40], b2b_active: bool, combo: u32
+    ) -> Result<(), CommandError> {
+        self.send.try_send(BotMsg::Reset {
+            field, b2b: b2b_active, combo
+        }).map_err(|e| if e.is_full() {
+            CommandError::Full
+        } else {
+            self.dead = true;
+            CommandError::Disconnected
+        })
+    }
 }
 
 enum BotMsg {
@@ -166,80 +488,181 @@ enum BotMsg {
     },
     NewPiece(Piece),
     NextMove,
+    NextMoveWithin(Duration),
     PrepareNextMove
 }
 
+/// The hard cap of a `NextMoveWithin` request, as a multiple of the requested duration: if
+/// `min_nodes` hasn't been reached by then, the bot is forced to return whatever move it
+/// currently considers best.
+const DEADLINE_HARD_CAP_MULTIPLIER: u32 = 4;
+
 #[derive(Debug)]
 enum BotResult {
     Move(Move),
     BotInfo(Info)
 }
 
-fn run(
-    recv: Receiver<BotMsg>,
-    send: Sender<BotResult>,
-    board: Board,
-    mut evaluator: impl Evaluator,
-    options: Options
-) {
-    send.send(BotResult::BotInfo({
-        let mut info = evaluator.info();
-        info.insert(0, ("Cold Clear".to_string(), None));
-        info
-    })).ok();
+/// Drains every `BotMsg` immediately available, starting from `first`. If a `Reset` shows up
+/// anywhere in the batch, everything queued ahead of the last one is discarded, `NewPiece`
+/// messages included. A `Reset` keeps the current board's next-piece queue and only replaces
+/// the field, combo and back-to-back status, so a piece dropped here is not added to that queue.
+fn drain_coalescing_resets(recv: &Receiver<BotMsg>, first: BotMsg) -> Vec<BotMsg> {
+    let mut batch = vec![first];
+    while let Ok(msg) = recv.try_recv() {
+        batch.push(msg);
+    }
+    if let Some(last_reset) = batch.iter().rposition(|msg| matches!(msg, BotMsg::Reset { .. })) {
+        batch.drain(..last_reset);
+    }
+    batch
+}
 
-    let mut tree = Tree::new(
-        board,
-        &Default::default(),
-        false,
-        &mut evaluator
-    );
+/// Whether a pending `do_move` request should actually be granted right now.
+///
+/// Ordinarily a move is only handed out once the tree has grown past `min_nodes`, so that
+/// `into_best_child` has more than the single forced root child to choose from. The deadline
+/// path is the exception: once `NextMoveWithin`'s timer actually fires, `min_nodes` having been
+/// merely *reached* (not exceeded) is good enough, since waiting for one more `tree.extend` pass
+/// just to satisfy a strict `>` would mean missing the deadline for no benefit. `forced_by_deadline`
+/// bypasses the node count entirely, since the hard cap exists precisely to hand back a move when
+/// the tree is still too small.
+fn should_emit_move(
+    do_move: bool,
+    deadline_fired: bool,
+    forced_by_deadline: bool,
+    child_nodes: usize,
+    min_nodes: usize
+) -> bool {
+    do_move && (child_nodes > min_nodes
+        || (deadline_fired && child_nodes >= min_nodes)
+        || forced_by_deadline)
+}
 
+/// The message loop shared by `run` and `run_parallel`.
+///
+/// Both flavors react to the same `BotMsg`s the same way and drive the same `min_nodes`/
+/// `max_nodes`/deadline state machine; the only thing that differs between them is *how* the
+/// tree gets bigger. `expand` does that: given the tree and the evaluator, it grows the tree by
+/// whatever means the caller prefers (extending in place, or farming leaves out to a worker
+/// pool) and reports whether the tree died. `on_reset` is a hook for invalidating any
+/// expansion-strategy state (e.g. a parallel work queue) that belongs to the tree a `Reset` just
+/// threw away.
+fn run_loop<E: Evaluator>(
+    recv: Receiver<BotMsg>,
+    send: UnboundedSender<BotResult>,
+    mut tree: Tree,
+    mut evaluator: E,
+    options: Options,
+    mut expand: impl FnMut(&mut Tree, &mut E) -> bool,
+    mut on_reset: impl FnMut()
+) {
     let mut do_move = false;
+    // Set alongside `do_move` only when the deadline path (as opposed to a plain `NextMove`) is
+    // what granted the request; see `should_emit_move`. Cleared whenever `do_move` is.
+    let mut deadline_fired = false;
+    // Set once a `NextMoveWithin` request is pending; cleared once the move is actually sent.
+    let mut deadline: Option<Instant> = None;
+    let mut hard_deadline: Option<Instant> = None;
+
     loop {
-        let result = if tree.child_nodes < options.max_nodes {
-            recv.try_recv()
+        let timeout = match deadline {
+            Some(d) => after(d.saturating_duration_since(Instant::now())),
+            None => never()
+        };
+
+        // If the tree is already as big as we're allowed to grow it and we're not waiting on a
+        // deadline, there's nothing useful to do until the next message arrives, so block for it
+        // instead of busy-polling. Otherwise, fall through to `default` so `expand` below keeps
+        // running between messages.
+        let event = if tree.child_nodes >= options.max_nodes && deadline.is_none() {
+            select! {
+                recv(recv) -> msg => match msg {
+                    Ok(msg) => Some(Ok(msg)),
+                    Err(_) => None
+                },
+                recv(timeout) -> _ => Some(Err(())),
+            }
         } else {
-            recv.recv().map_err(|_| TryRecvError::Disconnected)
+            select! {
+                recv(recv) -> msg => match msg {
+                    Ok(msg) => Some(Ok(msg)),
+                    Err(_) => None
+                },
+                recv(timeout) -> _ => Some(Err(())),
+                default => Some(Err(())),
+            }
         };
-        match result {
-            Err(TryRecvError::Empty) => {}
-            Err(TryRecvError::Disconnected) => break,
-            Ok(BotMsg::NewPiece(piece)) => if tree.add_next_piece(piece) {
-                // Only death is possible
-                break
+
+        match event {
+            None => break,
+            Some(Err(())) => if deadline.is_some_and(|d| Instant::now() >= d) &&
+                    (tree.child_nodes >= options.min_nodes ||
+                        hard_deadline.is_some_and(|h| Instant::now() >= h)) {
+                do_move = true;
+                deadline_fired = true;
             }
-            Ok(BotMsg::Reset {
-                field, b2b, combo
-            }) => {
-                let mut board = tree.board;
-                board.set_field(field);
-                board.combo = combo;
-                board.b2b_bonus = b2b;
-                tree = Tree::new(
-                    board,
-                    &Default::default(),
-                    false,
-                    &mut evaluator
-                );
+            Some(Ok(msg)) => {
+                let mut died = false;
+                for msg in drain_coalescing_resets(&recv, msg) {
+                    match msg {
+                        BotMsg::NewPiece(piece) => if tree.add_next_piece(piece) {
+                            // Only death is possible
+                            died = true;
+                            break
+                        }
+                        BotMsg::Reset { field, b2b, combo } => {
+                            let mut board = tree.board;
+                            board.set_field(field);
+                            board.combo = combo;
+                            board.b2b_bonus = b2b;
+                            tree = Tree::new(
+                                board,
+                                &Default::default(),
+                                false,
+                                &mut evaluator
+                            );
+                            // A pending move request was for the discarded tree; granting it
+                            // against the fresh, empty one would hand back a move chosen from a
+                            // single unexamined child.
+                            do_move = false;
+                            deadline_fired = false;
+                            // A pending deadline was timing the discarded tree; it has nothing to
+                            // do with the freshly reset one.
+                            deadline = None;
+                            hard_deadline = None;
+                            on_reset();
+                        }
+                        BotMsg::NextMove => do_move = true,
+                        BotMsg::NextMoveWithin(duration) => {
+                            let now = Instant::now();
+                            deadline = Some(now + duration);
+                            hard_deadline = Some(now + duration * DEADLINE_HARD_CAP_MULTIPLIER);
+                        }
+                        BotMsg::PrepareNextMove => {}
+                    }
+                }
+                if died {
+                    break
+                }
             }
-            Ok(BotMsg::NextMove) => do_move = true,
-            Ok(BotMsg::PrepareNextMove) => {}
         }
 
-        if do_move && tree.child_nodes > options.min_nodes {
+        let forced_by_deadline = hard_deadline.is_some_and(|h| Instant::now() >= h);
+        if should_emit_move(do_move, deadline_fired, forced_by_deadline, tree.child_nodes, options.min_nodes) {
             let moves_considered = tree.child_nodes;
             match tree.into_best_child() {
                 Ok(child) => {
                     do_move = false;
-                    if send.send(BotResult::Move(Move {
+                    deadline_fired = false;
+                    deadline = None;
+                    hard_deadline = None;
+                    let mut disconnected = send.unbounded_send(BotResult::Move(Move {
                         hold: child.hold,
                         inputs: child.mv.inputs,
                         expected_location: child.mv.location
-                    })).is_err() {
-                        return
-                    }
-                    if send.send(BotResult::BotInfo({
+                    })).is_err();
+                    disconnected |= send.unbounded_send(BotResult::BotInfo({
                         let mut info = evaluator.info();
                         info.insert(0, ("Cold Clear".to_owned(), None));
                         info.push(("Depth".to_owned(), Some(format!("{}", child.tree.depth))));
@@ -248,10 +671,11 @@ fn run(
                         info.push(("Nodes".to_owned(), Some("".to_owned())));
                         info.push(("".to_owned(), Some(format!("{}", moves_considered))));
                         info
-                    })).is_err() {
-                        return
-                    }
+                    })).is_err();
                     tree = child.tree;
+                    if disconnected {
+                        break
+                    }
                 }
                 Err(t) => tree = t
             }
@@ -259,8 +683,270 @@ fn run(
 
         if tree.child_nodes < options.max_nodes &&
                 tree.board.next_queue().count() > 0 &&
-                tree.extend(options, &mut evaluator) {
+                expand(&mut tree, &mut evaluator) {
             break
         }
     }
 }
+
+fn run(
+    recv: Receiver<BotMsg>,
+    send: UnboundedSender<BotResult>,
+    board: Board,
+    mut evaluator: impl Evaluator,
+    options: Options
+) {
+    send.unbounded_send(BotResult::BotInfo({
+        let mut info = evaluator.info();
+        info.insert(0, ("Cold Clear".to_string(), None));
+        info
+    })).ok();
+
+    let tree = Tree::new(
+        board,
+        &Default::default(),
+        false,
+        &mut evaluator
+    );
+
+    run_loop(
+        recv, send, tree, evaluator, options,
+        |tree, evaluator| tree.extend(options, evaluator),
+        || {}
+    );
+}
+
+/// Same as `run`, but spreads leaf expansion across `options.threads` worker threads instead of
+/// doing it all on this thread.
+///
+/// The bot thread remains the sole owner of `tree`, so `min_nodes`/`max_nodes` bookkeeping and
+/// move selection work exactly as in the single-threaded `run`. Workers only ever evaluate leaves
+/// and generate their children; splicing the results back into the tree and back-propagating is
+/// still done here, one batch at a time.
+fn run_parallel(
+    recv: Receiver<BotMsg>,
+    send: UnboundedSender<BotResult>,
+    board: Board,
+    evaluator: impl Evaluator + Clone + Send + 'static,
+    options: Options
+) {
+    let mut evaluator = evaluator;
+
+    send.unbounded_send(BotResult::BotInfo({
+        let mut info = evaluator.info();
+        info.insert(0, ("Cold Clear".to_string(), None));
+        info
+    })).ok();
+
+    let tree = Tree::new(
+        board,
+        &Default::default(),
+        false,
+        &mut evaluator
+    );
+
+    // Bounded so a flood of leaves can't outrun the coordinator splicing their results back in;
+    // the coordinator only ever enqueues as many leaves as there's room for. Each leaf/result is
+    // tagged with the reset epoch it was seeded under; see `epoch` below for why.
+    let queue: Arc<ArrayQueue<(u64, Leaf)>> = Arc::new(ArrayQueue::new(options.threads * 4));
+    let shutdown = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let (result_send, result_recv) = unbounded::<(u64, ExpansionResult)>();
+
+    let workers: Vec<_> = (0..options.threads).map(|_| {
+        let queue = queue.clone();
+        let shutdown = shutdown.clone();
+        let result_send = result_send.clone();
+        let mut evaluator = evaluator.clone();
+        std::thread::spawn(move || loop {
+            match queue.pop() {
+                Some((epoch, leaf)) => if result_send.send((epoch, leaf.evaluate(&mut evaluator))).is_err() {
+                    break
+                }
+                None => if shutdown.load(std::sync::atomic::Ordering::Relaxed) {
+                    break
+                } else {
+                    std::thread::yield_now()
+                }
+            }
+        })
+    }).collect();
+    drop(result_send);
+
+    // Bumped every time a Reset discards the tree. A worker can be mid-`leaf.evaluate()` when
+    // that happens, so draining `queue`/`result_recv` on reset can't catch a result that's
+    // already in flight; it'll still land in `result_recv`, just after the fresh tree is
+    // installed. Tagging every leaf/result with the epoch it was seeded under lets the
+    // coordinator recognize and drop that stale result instead of splicing it into the wrong
+    // tree.
+    let epoch = std::cell::Cell::new(0u64);
+
+    run_loop(
+        recv, send, tree, evaluator, options,
+        |tree, _evaluator| {
+            let current_epoch = epoch.get();
+            for leaf in tree.take_leaves_for_expansion(queue.capacity() - queue.len()) {
+                if queue.push((current_epoch, leaf)).is_err() {
+                    break
+                }
+            }
+            let mut died = false;
+            while let Ok((result_epoch, result)) = result_recv.try_recv() {
+                if result_epoch != current_epoch {
+                    // Computed for a tree that a Reset has since thrown away.
+                    continue;
+                }
+                if tree.apply_expansion(result) {
+                    died = true;
+                    break
+                }
+            }
+            died
+        },
+        || {
+            epoch.set(epoch.get().wrapping_add(1));
+            // Not required for correctness now that results are epoch-tagged, but draining here
+            // still saves the workers from grinding on leaves whose results are already known to
+            // be discarded.
+            while queue.pop().is_some() {}
+            while result_recv.try_recv().is_ok() {}
+        }
+    );
+
+    shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+    for worker in workers {
+        worker.join().ok();
+    }
+}
+
+#[cfg(test)]
+mod deadline_tests {
+    use super::*;
+
+    #[test]
+    fn plain_request_needs_more_than_min_nodes() {
+        // With min_nodes: 0 (the default), a plain `NextMove` must not fire before even one
+        // `tree.extend` has run, or the bot would always answer with a single, unexamined child.
+        assert!(!should_emit_move(true, false, false, 0, 0));
+        assert!(should_emit_move(true, false, false, 1, 0));
+    }
+
+    #[test]
+    fn fired_deadline_accepts_nodes_equal_to_min_nodes() {
+        assert!(should_emit_move(true, true, false, 5, 5));
+        assert!(!should_emit_move(true, false, false, 5, 5));
+    }
+
+    #[test]
+    fn hard_deadline_forces_regardless_of_node_count() {
+        assert!(should_emit_move(true, false, true, 0, 100));
+    }
+
+    #[test]
+    fn nothing_fires_without_a_pending_request() {
+        assert!(!should_emit_move(false, true, true, 100, 0));
+    }
+}
+
+#[cfg(test)]
+mod coalescing_reset_tests {
+    use super::*;
+
+    fn reset() -> BotMsg {
+        BotMsg::Reset { field: [[false; 10]; 40], b2b: false, combo: 0 }
+    }
+
+    #[test]
+    fn keeps_the_whole_batch_when_no_reset_is_present() {
+        let (send, recv) = crossbeam_channel::unbounded();
+        send.send(BotMsg::NextMove).unwrap();
+        send.send(BotMsg::PrepareNextMove).unwrap();
+        let first = recv.try_recv().unwrap();
+
+        let batch = drain_coalescing_resets(&recv, first);
+
+        assert_eq!(batch.len(), 2);
+        assert!(matches!(batch[0], BotMsg::NextMove));
+        assert!(matches!(batch[1], BotMsg::PrepareNextMove));
+    }
+
+    #[test]
+    fn discards_everything_queued_before_the_last_reset() {
+        let (send, recv) = crossbeam_channel::unbounded();
+        send.send(BotMsg::NewPiece(Piece::T)).unwrap();
+        send.send(BotMsg::NextMove).unwrap();
+        send.send(reset()).unwrap();
+        send.send(BotMsg::NextMove).unwrap();
+        let first = recv.try_recv().unwrap();
+
+        let batch = drain_coalescing_resets(&recv, first);
+
+        assert_eq!(batch.len(), 2);
+        assert!(matches!(batch[0], BotMsg::Reset { .. }));
+        assert!(matches!(batch[1], BotMsg::NextMove));
+    }
+
+    #[test]
+    fn keeps_only_the_messages_after_the_last_of_several_resets() {
+        let (send, recv) = crossbeam_channel::unbounded();
+        send.send(reset()).unwrap();
+        send.send(BotMsg::NewPiece(Piece::T)).unwrap();
+        send.send(reset()).unwrap();
+        let first = recv.try_recv().unwrap();
+
+        let batch = drain_coalescing_resets(&recv, first);
+
+        assert_eq!(batch.len(), 1);
+        assert!(matches!(batch[0], BotMsg::Reset { .. }));
+    }
+}
+
+#[cfg(test)]
+mod info_broadcast_tests {
+    use super::*;
+
+    fn info(label: &str) -> Info {
+        vec![(label.to_string(), None)]
+    }
+
+    #[test]
+    fn catches_up_subscriber_sees_nothing_until_a_new_push() {
+        let broadcast = InfoBroadcast::new();
+        broadcast.push(info("a"));
+        let mut receiver = InfoReceiver { broadcast: Arc::new(broadcast), next: 0 };
+
+        // Move the cursor to the current latest_seq, as `subscribe_info` would.
+        let next = receiver.broadcast.latest_seq();
+        receiver.next = next;
+        assert_eq!(receiver.try_recv(), Err(InfoRecvError::Empty));
+
+        receiver.broadcast.push(info("b"));
+        assert_eq!(receiver.try_recv().unwrap(), info("b"));
+    }
+
+    #[test]
+    fn reads_each_snapshot_once_in_order() {
+        let broadcast = Arc::new(InfoBroadcast::new());
+        broadcast.push(info("a"));
+        broadcast.push(info("b"));
+        let mut receiver = InfoReceiver { broadcast: broadcast.clone(), next: 0 };
+
+        assert_eq!(receiver.try_recv().unwrap(), info("a"));
+        assert_eq!(receiver.try_recv().unwrap(), info("b"));
+        assert_eq!(receiver.try_recv(), Err(InfoRecvError::Empty));
+    }
+
+    #[test]
+    fn lagging_subscriber_is_fast_forwarded_to_the_oldest_retained_snapshot() {
+        let broadcast = Arc::new(InfoBroadcast::new());
+        for i in 0..(INFO_RETENTION + 3) {
+            broadcast.push(info(&i.to_string()));
+        }
+        // This subscriber never read anything, so it's 3 snapshots behind the retained window.
+        let mut receiver = InfoReceiver { broadcast: broadcast.clone(), next: 0 };
+
+        assert_eq!(receiver.try_recv(), Err(InfoRecvError::Lagged(3)));
+        // The cursor is fast-forwarded, so the very next read succeeds with the oldest retained
+        // snapshot rather than reporting the same lag again.
+        assert_eq!(receiver.try_recv().unwrap(), info("3"));
+    }
+}
@@ -0,0 +1,115 @@
+//! A bounded, lock-free, multi-producer multi-consumer ring buffer, following the classic
+//! Vyukov bounded MPMC queue design: every slot carries a `sequence` stamp alongside its value,
+//! and a producer/consumer claims a slot by CAS'ing the shared enqueue/dequeue cursor once the
+//! slot's stamp shows it's actually their turn. The stamp is bumped by one lap's worth (the
+//! buffer length) on the opposite operation, so a claim made on the next lap around the ring
+//! can't be confused with this one.
+//!
+//! `run_parallel` uses this instead of pulling in `crossbeam-queue` so the queue actually lives
+//! in this crate, which is what its original request asked for.
+
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{ AtomicUsize, Ordering };
+
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>
+}
+
+/// A bounded MPMC queue; see the module docs for the algorithm.
+pub struct ArrayQueue<T> {
+    buffer: Box<[Slot<T>]>,
+    mask: usize,
+    enqueue_pos: AtomicUsize,
+    dequeue_pos: AtomicUsize
+}
+
+unsafe impl<T: Send> Send for ArrayQueue<T> {}
+unsafe impl<T: Send> Sync for ArrayQueue<T> {}
+
+impl<T> ArrayQueue<T> {
+    /// Creates a queue that can hold at least `capacity` elements. The ring is sized up to the
+    /// next power of two, since the classic Vyukov design indexes slots with a mask rather than
+    /// a modulo.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1).next_power_of_two();
+        let buffer = (0..capacity)
+            .map(|i| Slot { sequence: AtomicUsize::new(i), value: UnsafeCell::new(MaybeUninit::uninit()) })
+            .collect();
+        ArrayQueue {
+            buffer,
+            mask: capacity - 1,
+            enqueue_pos: AtomicUsize::new(0),
+            dequeue_pos: AtomicUsize::new(0)
+        }
+    }
+
+    /// The number of slots in the ring buffer (possibly more than what was requested in `new`,
+    /// rounded up to a power of two).
+    pub fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    /// An approximation of how many elements are currently queued; only exact if no producer or
+    /// consumer is concurrently active.
+    pub fn len(&self) -> usize {
+        let dequeue_pos = self.dequeue_pos.load(Ordering::Acquire);
+        let enqueue_pos = self.enqueue_pos.load(Ordering::Acquire);
+        enqueue_pos.wrapping_sub(dequeue_pos)
+    }
+
+    /// Attempts to push `value` onto the queue, handing it back if the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let seq = slot.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos as isize;
+            if diff == 0 {
+                if self.enqueue_pos.compare_exchange_weak(
+                    pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed
+                ).is_ok() {
+                    unsafe { (*slot.value.get()).write(value); }
+                    slot.sequence.store(pos.wrapping_add(1), Ordering::Release);
+                    return Ok(());
+                }
+                pos = self.enqueue_pos.load(Ordering::Relaxed);
+            } else if diff < 0 {
+                return Err(value);
+            } else {
+                pos = self.enqueue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Attempts to pop the oldest queued value, returning `None` if the queue is empty.
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let seq = slot.sequence.load(Ordering::Acquire);
+            let diff = seq as isize - pos.wrapping_add(1) as isize;
+            if diff == 0 {
+                if self.dequeue_pos.compare_exchange_weak(
+                    pos, pos.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed
+                ).is_ok() {
+                    let value = unsafe { (*slot.value.get()).assume_init_read() };
+                    slot.sequence.store(pos.wrapping_add(self.mask).wrapping_add(1), Ordering::Release);
+                    return Some(value);
+                }
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            } else if diff < 0 {
+                return None;
+            } else {
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl<T> Drop for ArrayQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}